@@ -0,0 +1,144 @@
+use crate::types::{StructEntry, TypeKind, TypeLayout};
+use std::collections::HashMap;
+
+#[derive(Debug, PartialEq)]
+pub struct FieldChange {
+    pub name: String,
+    pub before_size: u64,
+    pub after_size: u64,
+    pub delta: i64,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct TypeDelta {
+    pub name: String,
+    pub size_before: u64,
+    pub size_after: u64,
+    pub size_delta: i64,
+    pub alignment_before: u64,
+    pub alignment_after: u64,
+    pub alignment_delta: i64,
+    pub field_changes: Vec<FieldChange>,
+}
+
+#[derive(Debug, PartialEq, Default)]
+pub struct LayoutDiff {
+    pub changed: Vec<TypeDelta>,
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+}
+
+/// Matches `before`/`after` type-size dumps by name and reports per-type
+/// size/alignment deltas, newly added/removed types, and per-field size
+/// changes for matched structs. `changed` is sorted by largest absolute size
+/// change first (growth and shrinkage alike), so the types most worth
+/// looking at land at the top.
+pub fn diff_layouts(before: &[TypeLayout], after: &[TypeLayout]) -> LayoutDiff {
+    let before_by_name: HashMap<&str, &TypeLayout> =
+        before.iter().map(|l| (l.name.as_str(), l)).collect();
+    let after_by_name: HashMap<&str, &TypeLayout> =
+        after.iter().map(|l| (l.name.as_str(), l)).collect();
+
+    let mut changed = Vec::new();
+    let mut added = Vec::new();
+    for after_layout in after {
+        match before_by_name.get(after_layout.name.as_str()) {
+            Some(before_layout) => {
+                if before_layout.size != after_layout.size
+                    || before_layout.alignment != after_layout.alignment
+                    || struct_fields(before_layout) != struct_fields(after_layout)
+                {
+                    changed.push(build_delta(before_layout, after_layout));
+                }
+            }
+            None => added.push(after_layout.name.clone()),
+        }
+    }
+
+    let removed = before
+        .iter()
+        .filter(|l| !after_by_name.contains_key(l.name.as_str()))
+        .map(|l| l.name.clone())
+        .collect();
+
+    changed.sort_by_key(|d| std::cmp::Reverse(d.size_delta.abs()));
+
+    LayoutDiff {
+        changed,
+        added,
+        removed,
+    }
+}
+
+/// Flattened `(name, size)` pairs for a struct's fields and padding runs, in
+/// layout order. Padding is named `<padding>`; callers match entries by
+/// position rather than by this name, since a struct can have more than one
+/// padding run. Returns `None` for non-struct types.
+fn struct_fields(layout: &TypeLayout) -> Option<Vec<(&str, u64)>> {
+    match &layout.kind {
+        TypeKind::Struct { entries } => Some(
+            entries
+                .iter()
+                .map(|e| match e {
+                    StructEntry::Field(f) => (f.name.as_str(), f.size),
+                    StructEntry::Padding { size } => ("<padding>", *size),
+                    StructEntry::Upvar(c) | StructEntry::Local(c) => (c.name.as_str(), c.size),
+                })
+                .collect(),
+        ),
+        _ => None,
+    }
+}
+
+fn build_delta(before: &TypeLayout, after: &TypeLayout) -> TypeDelta {
+    let field_changes = match (struct_fields(before), struct_fields(after)) {
+        (Some(before_fields), Some(after_fields)) => {
+            let len = before_fields.len().max(after_fields.len());
+            (0..len)
+                .filter_map(|i| {
+                    match (before_fields.get(i), after_fields.get(i)) {
+                        (Some(&(b_name, b_size)), Some(&(a_name, a_size))) => {
+                            if b_name == a_name && b_size == a_size {
+                                None
+                            } else {
+                                Some(FieldChange {
+                                    name: a_name.to_string(),
+                                    before_size: b_size,
+                                    after_size: a_size,
+                                    delta: a_size as i64 - b_size as i64,
+                                })
+                            }
+                        }
+                        // A field present only in `before` was removed.
+                        (Some(&(b_name, b_size)), None) => Some(FieldChange {
+                            name: b_name.to_string(),
+                            before_size: b_size,
+                            after_size: 0,
+                            delta: -(b_size as i64),
+                        }),
+                        // A field present only in `after` was added.
+                        (None, Some(&(a_name, a_size))) => Some(FieldChange {
+                            name: a_name.to_string(),
+                            before_size: 0,
+                            after_size: a_size,
+                            delta: a_size as i64,
+                        }),
+                        (None, None) => None,
+                    }
+                })
+                .collect()
+        }
+        _ => Vec::new(),
+    };
+
+    TypeDelta {
+        name: after.name.clone(),
+        size_before: before.size,
+        size_after: after.size,
+        size_delta: after.size as i64 - before.size as i64,
+        alignment_before: before.alignment,
+        alignment_after: after.alignment,
+        alignment_delta: after.alignment as i64 - before.alignment as i64,
+        field_changes,
+    }
+}
@@ -2,12 +2,19 @@ use std::env;
 use std::fs::File;
 use std::io::{self, BufReader};
 
+mod diff;
 mod parse_layouts;
 mod types;
+use diff::diff_layouts;
 use parse_layouts::parse_layouts;
-use types::VerificationError;
+use types::{Tagging, TypeKind, VerificationError};
 
 fn main() -> io::Result<()> {
+    let mut args = env::args().skip(1);
+    if args.next().as_deref() == Some("diff") {
+        return run_diff(args.collect());
+    }
+
     let path = get_input_path();
     let layouts = parse_layouts(BufReader::new(File::open(path)?))?;
 
@@ -25,10 +32,62 @@ fn main() -> io::Result<()> {
         }
 
         if let Err(e) = layout.verify() {
-            println!("  - error reason: {}\n", format_verification_error(e));
+            println!("  - error reason: {}\n", format_verification_error(&e));
             found_error = true;
         }
 
+        if let Some(summary) = layout.coroutine_summary() {
+            println!(
+                "  - coroutine: peak state `{}` ({} bytes), saved locals: {:?}\n",
+                summary.peak_state, summary.peak_size, summary.saved_locals
+            );
+        }
+
+        if let TypeKind::Enum { tagging, .. } = &layout.kind {
+            match tagging {
+                Tagging::Tagged => println!("  - tagging: tagged\n"),
+                Tagging::Niche { niche_variant } => println!(
+                    "  - tagging: niche (variant: {})\n",
+                    niche_variant.as_deref().unwrap_or("unknown")
+                ),
+            }
+        }
+
+        if layout.repr.packed || layout.repr.over_aligned || layout.repr.likely_c {
+            println!(
+                "  - repr: packed={}, over_aligned={}, likely_c={}\n",
+                layout.repr.packed, layout.repr.over_aligned, layout.repr.likely_c
+            );
+        }
+
+        if let Some(byte_layout) = layout.verify_byte_layout() {
+            if !byte_layout.errors.is_empty() {
+                println!("  - byte layout errors:");
+                for err in &byte_layout.errors {
+                    println!("      {}", format_verification_error(err));
+                }
+                println!();
+                found_error = true;
+            }
+            println!("  - byte map:\n{}\n", byte_layout.render());
+        }
+
+        if let Some(optimized) = layout.optimize_layout() {
+            if optimized.savings > 0 {
+                println!(
+                    "  - optimization: reordering fields saves {} bytes ({} -> {})\n",
+                    optimized.savings, layout.size, optimized.size
+                );
+                for field in &optimized.fields {
+                    println!(
+                        "      {}: {} bytes, offset: {} bytes, alignment: {} bytes",
+                        field.name, field.size, field.offset, field.alignment
+                    );
+                }
+                println!();
+            }
+        }
+
         if found_error {
             println!("\x1b[1;31m{:#?}\x1b[0m", layout);
         } else {
@@ -42,11 +101,78 @@ fn main() -> io::Result<()> {
 fn get_input_path() -> String {
     env::args().nth(1).unwrap_or_else(|| {
         eprintln!("Usage: cargo r -- <type-sizes-path>");
+        eprintln!("       cargo r -- diff <old-path> <new-path> [--threshold <bytes>]");
         std::process::exit(1);
     })
 }
 
-fn format_verification_error(e: VerificationError) -> String {
+fn diff_usage() -> ! {
+    eprintln!("Usage: cargo r -- diff <old-path> <new-path> [--threshold <bytes>]");
+    std::process::exit(1);
+}
+
+/// Parses both dumps, reports per-type size/alignment deltas sorted by
+/// largest absolute size change, and exits non-zero if `--threshold` is
+/// given and any type grew by more than that many bytes — so CI can gate on
+/// layout regressions the way it gates on other size budgets.
+fn run_diff(args: Vec<String>) -> io::Result<()> {
+    let mut paths = Vec::new();
+    let mut threshold: Option<i64> = None;
+
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        if arg == "--threshold" {
+            let value = args.next().unwrap_or_else(|| diff_usage());
+            threshold = Some(value.parse().unwrap_or_else(|_| diff_usage()));
+        } else {
+            paths.push(arg);
+        }
+    }
+
+    let (before_path, after_path) = match &paths[..] {
+        [before, after] => (before, after),
+        _ => diff_usage(),
+    };
+
+    let before = parse_layouts(BufReader::new(File::open(before_path)?))?;
+    let after = parse_layouts(BufReader::new(File::open(after_path)?))?;
+    let result = diff_layouts(&before, &after);
+
+    for delta in &result.changed {
+        println!(
+            "~ {}: size {} -> {} ({:+}), alignment {} -> {} ({:+})",
+            delta.name,
+            delta.size_before,
+            delta.size_after,
+            delta.size_delta,
+            delta.alignment_before,
+            delta.alignment_after,
+            delta.alignment_delta
+        );
+        for change in &delta.field_changes {
+            println!(
+                "    {}: {} -> {} ({:+})",
+                change.name, change.before_size, change.after_size, change.delta
+            );
+        }
+    }
+    for name in &result.added {
+        println!("+ {}", name);
+    }
+    for name in &result.removed {
+        println!("- {}", name);
+    }
+
+    if let Some(threshold) = threshold {
+        if result.changed.iter().any(|d| d.size_delta > threshold) {
+            std::process::exit(1);
+        }
+    }
+
+    Ok(())
+}
+
+fn format_verification_error(e: &VerificationError) -> String {
     use VerificationError::*;
     match e {
         StructSizeMismatch { expected, actual } => format!(
@@ -75,5 +201,20 @@ fn format_verification_error(e: VerificationError) -> String {
             "mismatch enum size (expected: {}, calculated_min: {})",
             expected, calculated_min
         ),
+        OverlappingFields { first, second, at } => format!(
+            "overlapping fields (first: {}, second: {}, at byte: {})",
+            first, second, at
+        ),
+        UnaccountedGap { start, end } => {
+            format!("unaccounted gap (bytes {}..{})", start, end)
+        }
+        MisalignedOffset {
+            field_name,
+            offset,
+            alignment,
+        } => format!(
+            "misaligned offset (field: {}, offset: {}, alignment: {})",
+            field_name, offset, alignment
+        ),
     }
 }
@@ -1,4 +1,6 @@
-use crate::types::{ClosureVar, Field, StructEntry, TypeKind, TypeLayout, Variant};
+use crate::types::{
+    ClosureVar, Field, ReprInfo, StructEntry, Tagging, TypeKind, TypeLayout, Variant,
+};
 use lazy_static::lazy_static;
 use regex::Regex;
 use std::io::{self, BufRead};
@@ -11,9 +13,12 @@ lazy_static! {
     static ref RE_VARIANT: Regex = Regex::new(r"^\s*print-type-size\s+variant `(.+?)`: (\d+) bytes").unwrap();
     static ref RE_DISCRIMINANT: Regex = Regex::new(r"^\s*print-type-size\s+discriminant: (\d+) bytes").unwrap();
     static ref RE_UPVAR: Regex = Regex::new(r"^\s*print-type-size\s+upvar `(.+?)`: (\d+) bytes(?:, offset: (\d+) bytes, alignment: (\d+) bytes)?").unwrap();
-    static ref RE_LOCAL: Regex = Regex::new(r"^\s*print-type-size\s+local `(.+?)`: (\d+) bytes(?:, type: (.+))?").unwrap();
+    static ref RE_LOCAL: Regex = Regex::new(r"^\s*print-type-size\s+local `(.+?)`: (\d+) bytes(.*)").unwrap();
     static ref RE_ATTR_OFFSET: Regex = Regex::new(r"offset: (\d+)").unwrap();
     static ref RE_ATTR_ALIGN: Regex = Regex::new(r"alignment: (\d+)").unwrap();
+    static ref RE_ATTR_TYPE: Regex = Regex::new(r"type: (.+)$").unwrap();
+    static ref RE_COROUTINE_STATE: Regex =
+        Regex::new(r"^(Unresumed|Returned|Panicked|Suspend\d+)$").unwrap();
 }
 
 pub fn parse_layouts(reader: impl BufRead) -> io::Result<Vec<TypeLayout>> {
@@ -27,6 +32,15 @@ pub fn parse_layouts(reader: impl BufRead) -> io::Result<Vec<TypeLayout>> {
                 variants.push(variant);
             }
         }
+        if let TypeKind::Struct { .. } = &layout.kind {
+            layout.repr = layout.infer_repr();
+        }
+        if let TypeKind::Enum { .. } = &layout.kind {
+            let tagging = layout.infer_tagging().unwrap_or(Tagging::Tagged);
+            if let TypeKind::Enum { tagging: t, .. } = &mut layout.kind {
+                *t = tagging;
+            }
+        }
         if let TypeKind::Enum { variants, .. } = &mut layout.kind {
             if variants.len() == 1 && variants[0].name == layout.name {
                 let union_variant = variants.remove(0);
@@ -41,6 +55,35 @@ pub fn parse_layouts(reader: impl BufRead) -> io::Result<Vec<TypeLayout>> {
                 layout.kind = TypeKind::Union { fields };
             }
         }
+        let looks_like_coroutine = match &layout.kind {
+            TypeKind::Enum { variants, .. } => {
+                !variants.is_empty()
+                    && variants
+                        .iter()
+                        .all(|v| RE_COROUTINE_STATE.is_match(&v.name))
+                    && variants
+                        .iter()
+                        .any(|v| v.entries.iter().any(|e| matches!(e, StructEntry::Local(_))))
+            }
+            _ => false,
+        };
+        if looks_like_coroutine {
+            if let TypeKind::Enum {
+                discriminant_size,
+                variants,
+                ..
+            } = std::mem::replace(
+                &mut layout.kind,
+                TypeKind::Struct {
+                    entries: Vec::new(),
+                },
+            ) {
+                layout.kind = TypeKind::Coroutine {
+                    discriminant_size,
+                    states: variants,
+                };
+            }
+        }
     };
 
     for line_result in reader.lines() {
@@ -61,6 +104,7 @@ pub fn parse_layouts(reader: impl BufRead) -> io::Result<Vec<TypeLayout>> {
                 kind: TypeKind::Struct {
                     entries: Vec::new(),
                 },
+                repr: ReprInfo::default(),
                 unhandled_lines: Vec::new(),
                 raw_lines: vec![original_line],
             });
@@ -92,6 +136,7 @@ pub fn parse_layouts(reader: impl BufRead) -> io::Result<Vec<TypeLayout>> {
                 layout.kind = TypeKind::Enum {
                     discriminant_size: 0,
                     variants: Vec::new(),
+                    tagging: Tagging::Tagged,
                 };
             }
         } else if let Some(caps) = RE_DISCRIMINANT.captures(&original_line) {
@@ -105,6 +150,7 @@ pub fn parse_layouts(reader: impl BufRead) -> io::Result<Vec<TypeLayout>> {
                 layout.kind = TypeKind::Enum {
                     discriminant_size: caps[1].parse().unwrap(),
                     variants: Vec::new(),
+                    tagging: Tagging::Tagged,
                 };
             }
         } else if let Some(caps) = RE_UPVAR.captures(&original_line) {
@@ -121,12 +167,19 @@ pub fn parse_layouts(reader: impl BufRead) -> io::Result<Vec<TypeLayout>> {
             }
         } else if let Some(caps) = RE_LOCAL.captures(&original_line) {
             handled = true;
+            let attributes_str = caps.get(3).unwrap().as_str();
             let var = ClosureVar {
                 name: caps.get(1).unwrap().as_str().to_string(),
                 size: caps.get(2).unwrap().as_str().parse().unwrap(),
-                offset: None,
-                alignment: None,
-                type_info: caps.get(3).map(|m| m.as_str().to_string()),
+                offset: RE_ATTR_OFFSET
+                    .captures(attributes_str)
+                    .map(|c| c[1].parse().unwrap()),
+                alignment: RE_ATTR_ALIGN
+                    .captures(attributes_str)
+                    .map(|c| c[1].parse().unwrap()),
+                type_info: RE_ATTR_TYPE
+                    .captures(attributes_str)
+                    .map(|c| c[1].to_string()),
             };
             if let Some(variant) = current_variant.as_mut() {
                 variant.entries.push(StructEntry::Local(var));
@@ -30,6 +30,16 @@ pub struct Variant {
     pub entries: Vec<StructEntry>,
 }
 
+/// How an enum's discriminant is stored, per rustc's ABI layout.
+#[derive(Debug, PartialEq, Clone)]
+pub enum Tagging {
+    /// Reserves `discriminant_size` bytes on top of the largest variant.
+    Tagged,
+    /// The discriminant is encoded in an unused bit-pattern of a variant's
+    /// field, so the enum is no larger than its largest variant.
+    Niche { niche_variant: Option<String> },
+}
+
 #[derive(Debug, PartialEq)]
 pub enum TypeKind {
     Struct {
@@ -38,10 +48,36 @@ pub enum TypeKind {
     Enum {
         discriminant_size: u64,
         variants: Vec<Variant>,
+        tagging: Tagging,
     },
     Union {
         fields: Vec<Field>,
     },
+    /// A coroutine/async-fn state machine, printed by rustc as an enum whose
+    /// variants are its resume states (`Unresumed`, `Suspend0`, `Returned`, ...).
+    /// Unlike an ordinary enum's variant fields, `local` entries for
+    /// overlapping states are aliased onto the same bytes rather than laid
+    /// out sequentially.
+    Coroutine {
+        discriminant_size: u64,
+        states: Vec<Variant>,
+    },
+}
+
+/// `repr` attributes inferred from the layout itself, since `print-type-size`
+/// doesn't echo the source-level `#[repr(...)]` attribute.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct ReprInfo {
+    /// Fields sit at offsets that aren't a multiple of their own alignment,
+    /// or the struct's alignment is smaller than its widest field — only
+    /// possible under `repr(packed)`.
+    pub packed: bool,
+    /// The struct's alignment exceeds its widest field's alignment, which
+    /// only `repr(align(N))` can force.
+    pub over_aligned: bool,
+    /// Fields appear in declaration order rather than the alignment-greedy
+    /// order `repr(Rust)` would pick, suggesting `repr(C)`.
+    pub likely_c: bool,
 }
 
 #[derive(Debug, PartialEq)]
@@ -50,11 +86,27 @@ pub struct TypeLayout {
     pub size: u64,
     pub alignment: u64,
     pub kind: TypeKind,
+    pub repr: ReprInfo,
     pub unhandled_lines: Vec<String>,
     pub raw_lines: Vec<String>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
+pub struct OptimizedField {
+    pub name: String,
+    pub size: u64,
+    pub alignment: u64,
+    pub offset: u64,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct OptimizedLayout {
+    pub fields: Vec<OptimizedField>,
+    pub size: u64,
+    pub savings: u64,
+}
+
+#[derive(Debug, PartialEq)]
 pub enum VerificationError {
     StructSizeMismatch {
         expected: u64,
@@ -74,6 +126,46 @@ pub enum VerificationError {
         expected: u64,
         calculated_min: u64,
     },
+    OverlappingFields {
+        first: String,
+        second: String,
+        at: u64,
+    },
+    UnaccountedGap {
+        start: u64,
+        end: u64,
+    },
+    MisalignedOffset {
+        field_name: String,
+        offset: u64,
+        alignment: u64,
+    },
+}
+
+#[derive(Debug, PartialEq)]
+pub struct ByteRange {
+    pub start: u64,
+    pub end: u64,
+    pub label: String,
+    pub is_padding: bool,
+}
+
+#[derive(Debug, PartialEq, Default)]
+pub struct ByteLayoutReport {
+    pub ranges: Vec<ByteRange>,
+    pub errors: Vec<VerificationError>,
+}
+
+impl ByteLayoutReport {
+    /// Renders a compact ASCII map of byte ranges, one per line, e.g.
+    /// `[0, 4) foo` or `[4, 8) padding`.
+    pub fn render(&self) -> String {
+        self.ranges
+            .iter()
+            .map(|range| format!("[{}, {}) {}", range.start, range.end, range.label))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
 }
 
 impl StructEntry {
@@ -87,12 +179,30 @@ impl StructEntry {
     }
 }
 
+/// The state a coroutine peaks in (the state with the largest size) and
+/// which locals it's keeping alive there.
+#[derive(Debug, PartialEq)]
+pub struct CoroutineSummary {
+    pub peak_state: String,
+    pub peak_size: u64,
+    pub saved_locals: Vec<String>,
+}
+
 impl TypeLayout {
     pub fn verify(&self) -> Result<(), VerificationError> {
         match &self.kind {
             TypeKind::Struct { entries } => {
                 let calculated_size: u64 = entries.iter().map(|e| e.size()).sum();
-                if self.size == calculated_size {
+                // repr(packed) suppresses the inter-field padding rustc would
+                // otherwise inject, so a packed struct's entries may
+                // legitimately sum to less than the declared size instead of
+                // matching it exactly.
+                let size_matches = if self.repr.packed {
+                    calculated_size <= self.size
+                } else {
+                    calculated_size == self.size
+                };
+                if size_matches {
                     Ok(())
                 } else {
                     Err(VerificationError::StructSizeMismatch {
@@ -104,6 +214,7 @@ impl TypeLayout {
             TypeKind::Enum {
                 variants,
                 discriminant_size,
+                tagging,
             } => {
                 for variant in variants {
                     let calculated_variant_size: u64 =
@@ -122,14 +233,20 @@ impl TypeLayout {
                     });
                 }
                 let max_variant_size = variants.iter().map(|v| v.size).max().unwrap_or(0);
-                let min_required_additive = discriminant_size + max_variant_size;
-                let min_required_niche = (*discriminant_size).max(max_variant_size);
-                if self.size >= min_required_additive || self.size == min_required_niche {
+                let min_required = match tagging {
+                    // A tagged enum reserves the discriminant on top of the
+                    // largest variant.
+                    Tagging::Tagged => discriminant_size + max_variant_size,
+                    // A niche-filled enum stores the discriminant inside a
+                    // variant's field, so it pays no extra bytes.
+                    Tagging::Niche { .. } => max_variant_size,
+                };
+                if self.size >= min_required {
                     Ok(())
                 } else {
                     Err(VerificationError::EnumTotalSizeMismatch {
                         expected: self.size,
-                        calculated_min: min_required_additive,
+                        calculated_min: min_required,
                     })
                 }
             }
@@ -144,6 +261,333 @@ impl TypeLayout {
                     })
                 }
             }
+            TypeKind::Coroutine {
+                discriminant_size,
+                states,
+            } => {
+                for state in states {
+                    let all_offsets_known = state
+                        .entries
+                        .iter()
+                        .all(|e| !matches!(e, StructEntry::Local(l) if l.offset.is_none()));
+                    if !all_offsets_known {
+                        continue;
+                    }
+                    // Locals within a state share storage (they're aliased
+                    // onto the same bytes), so the state's size is the high
+                    // watermark of `offset + size`, not their sum.
+                    let calculated_state_size = state
+                        .entries
+                        .iter()
+                        .filter_map(|e| match e {
+                            StructEntry::Local(l) => Some(l.offset.unwrap_or(0) + l.size),
+                            _ => None,
+                        })
+                        .max()
+                        .unwrap_or(0);
+                    // Only an outright overflow is an error: rustc can still
+                    // round the state up to the coroutine's alignment, and a
+                    // state's `Padding`/`Upvar` entries aren't summed above,
+                    // so `calculated_state_size` is a lower bound, not exact.
+                    if calculated_state_size > state.size {
+                        return Err(VerificationError::VariantSizeMismatch {
+                            variant_name: state.name.clone(),
+                            expected: state.size,
+                            actual: calculated_state_size,
+                        });
+                    }
+                }
+                let max_state_size = states.iter().map(|s| s.size).max().unwrap_or(0);
+                let min_required = discriminant_size + max_state_size;
+                if self.size >= min_required {
+                    Ok(())
+                } else {
+                    Err(VerificationError::EnumTotalSizeMismatch {
+                        expected: self.size,
+                        calculated_min: min_required,
+                    })
+                }
+            }
         }
     }
+
+    /// Identifies the coroutine state with the largest size (the one that
+    /// dominates the coroutine's overall size) and which locals it keeps
+    /// alive.
+    ///
+    /// Returns `None` for non-coroutine types.
+    pub fn coroutine_summary(&self) -> Option<CoroutineSummary> {
+        let states = match &self.kind {
+            TypeKind::Coroutine { states, .. } => states,
+            _ => return None,
+        };
+
+        let peak = states.iter().max_by_key(|s| s.size)?;
+        let saved_locals = peak
+            .entries
+            .iter()
+            .filter_map(|e| match e {
+                StructEntry::Local(l) => Some(l.name.clone()),
+                _ => None,
+            })
+            .collect();
+
+        Some(CoroutineSummary {
+            peak_state: peak.name.clone(),
+            peak_size: peak.size,
+            saved_locals,
+        })
+    }
+
+    /// Computes the field ordering rustc's `repr(Rust)` layout algorithm would
+    /// produce (fields sorted by descending alignment, ties broken by descending
+    /// size) and reports how many bytes the current layout wastes relative to it.
+    ///
+    /// Returns `None` for non-struct types and for structs where a field's
+    /// alignment wasn't recorded by `print-type-size`, since the greedy packing
+    /// can't be reconstructed without it.
+    pub fn optimize_layout(&self) -> Option<OptimizedLayout> {
+        let entries = match &self.kind {
+            TypeKind::Struct { entries } => entries,
+            _ => return None,
+        };
+
+        let mut fields: Vec<&Field> = entries
+            .iter()
+            .filter_map(|e| match e {
+                StructEntry::Field(f) => Some(f),
+                _ => None,
+            })
+            .collect();
+
+        if fields.iter().any(|f| f.alignment.is_none()) {
+            return None;
+        }
+
+        fields.sort_by(|a, b| {
+            b.alignment
+                .unwrap()
+                .cmp(&a.alignment.unwrap())
+                .then_with(|| b.size.cmp(&a.size))
+        });
+
+        let mut cursor = 0u64;
+        let mut packed = Vec::with_capacity(fields.len());
+        for field in fields {
+            let alignment = field.alignment.unwrap();
+            let offset = round_up(cursor, alignment);
+            packed.push(OptimizedField {
+                name: field.name.clone(),
+                size: field.size,
+                alignment,
+                offset,
+            });
+            cursor = offset + field.size;
+        }
+
+        let size = round_up(cursor, self.alignment);
+        let savings = self.size.saturating_sub(size);
+
+        Some(OptimizedLayout {
+            fields: packed,
+            size,
+            savings,
+        })
+    }
+
+    /// Infers likely `#[repr(...)]` attributes from the recorded sizes,
+    /// alignments, and offsets. `print-type-size` doesn't echo the source
+    /// attribute, but packed, over-aligned, and C layouts each leave a
+    /// distinct fingerprint on the numbers it does print.
+    pub fn infer_repr(&self) -> ReprInfo {
+        let entries = match &self.kind {
+            TypeKind::Struct { entries } => entries,
+            _ => return ReprInfo::default(),
+        };
+
+        let fields: Vec<&Field> = entries
+            .iter()
+            .filter_map(|e| match e {
+                StructEntry::Field(f) => Some(f),
+                _ => None,
+            })
+            .collect();
+
+        let max_field_alignment = fields
+            .iter()
+            .filter_map(|f| f.alignment)
+            .max()
+            .unwrap_or(self.alignment);
+
+        let packed = self.alignment < max_field_alignment
+            || fields.iter().any(|f| match (f.offset, f.alignment) {
+                (Some(offset), Some(alignment)) if alignment > 0 => offset % alignment != 0,
+                _ => false,
+            });
+
+        let over_aligned = self.alignment > max_field_alignment;
+
+        let likely_c = !packed
+            && self
+                .optimize_layout()
+                .map(|optimized| {
+                    let declared: Vec<&str> = fields.iter().map(|f| f.name.as_str()).collect();
+                    let greedy: Vec<&str> =
+                        optimized.fields.iter().map(|f| f.name.as_str()).collect();
+                    declared != greedy
+                })
+                .unwrap_or(false);
+
+        ReprInfo {
+            packed,
+            over_aligned,
+            likely_c,
+        }
+    }
+
+    /// Classifies an enum as niche-filled or tagged from its recorded sizes:
+    /// a niche enum is no larger than its biggest variant because the
+    /// discriminant is stowed inside an unused bit-pattern of one of that
+    /// variant's fields, while a tagged enum pays `discriminant_size` bytes
+    /// on top.
+    ///
+    /// Returns `None` for non-enum types.
+    pub fn infer_tagging(&self) -> Option<Tagging> {
+        let (discriminant_size, variants) = match &self.kind {
+            TypeKind::Enum {
+                discriminant_size,
+                variants,
+                ..
+            } => (*discriminant_size, variants),
+            _ => return None,
+        };
+
+        let max_variant_size = variants.iter().map(|v| v.size).max().unwrap_or(0);
+        // A niche-filled enum has no separate discriminant at all — the tag
+        // lives inside a field's unused bit-pattern. `discriminant_size > 0`
+        // means rustc emitted a real `discriminant: N bytes` line, which only
+        // a tagged enum has, even when that tag happens to fit in the same
+        // byte range as the largest variant.
+        let is_niche = self.size == max_variant_size && discriminant_size == 0;
+
+        if !is_niche {
+            return Some(Tagging::Tagged);
+        }
+
+        let niche_variant = variants
+            .iter()
+            .filter(|v| v.size == max_variant_size)
+            .max_by_key(|v| {
+                v.entries
+                    .iter()
+                    .filter_map(|e| match e {
+                        StructEntry::Field(f) => f.alignment,
+                        _ => None,
+                    })
+                    .max()
+                    .unwrap_or(0)
+            })
+            .map(|v| v.name.clone());
+
+        Some(Tagging::Niche { niche_variant })
+    }
+
+    /// Reconstructs the full byte range of every field (and padding run) in a
+    /// struct from its offsets, flagging overlaps, unaccounted gaps, and
+    /// offsets that aren't a multiple of their field's alignment. Misaligned
+    /// offsets are not flagged for structs inferred as `repr(packed)`.
+    ///
+    /// Returns `None` for non-struct types.
+    pub fn verify_byte_layout(&self) -> Option<ByteLayoutReport> {
+        let entries = match &self.kind {
+            TypeKind::Struct { entries } => entries,
+            _ => return None,
+        };
+
+        let mut report = ByteLayoutReport::default();
+        let mut cursor = 0u64;
+        let mut prev_field_name: Option<String> = None;
+
+        for entry in entries {
+            match entry {
+                StructEntry::Field(field) => {
+                    let start = field.offset.unwrap_or(cursor);
+
+                    if !self.repr.packed {
+                        if let Some(alignment) = field.alignment {
+                            if alignment > 0 && start % alignment != 0 {
+                                report.errors.push(VerificationError::MisalignedOffset {
+                                    field_name: field.name.clone(),
+                                    offset: start,
+                                    alignment,
+                                });
+                            }
+                        }
+                    }
+
+                    if start < cursor {
+                        report.errors.push(VerificationError::OverlappingFields {
+                            first: prev_field_name.clone().unwrap_or_default(),
+                            second: field.name.clone(),
+                            at: start,
+                        });
+                    } else if start > cursor {
+                        // rustc only prints a field's `offset:` when alignment
+                        // forced padding, so a gap that lines up with the
+                        // field's own alignment is expected implied padding,
+                        // not an anomaly — only flag it when it doesn't.
+                        let implied_by_alignment = field
+                            .alignment
+                            .map(|alignment| alignment > 0 && start == round_up(cursor, alignment))
+                            .unwrap_or(false);
+                        if implied_by_alignment {
+                            report.ranges.push(ByteRange {
+                                start: cursor,
+                                end: start,
+                                label: "padding (implied)".to_string(),
+                                is_padding: true,
+                            });
+                        } else {
+                            report.errors.push(VerificationError::UnaccountedGap {
+                                start: cursor,
+                                end: start,
+                            });
+                        }
+                    }
+
+                    let end = start + field.size;
+                    report.ranges.push(ByteRange {
+                        start,
+                        end,
+                        label: field.name.clone(),
+                        is_padding: false,
+                    });
+
+                    cursor = cursor.max(end);
+                    prev_field_name = Some(field.name.clone());
+                }
+                StructEntry::Padding { size } => {
+                    let start = cursor;
+                    let end = cursor + size;
+                    report.ranges.push(ByteRange {
+                        start,
+                        end,
+                        label: "padding".to_string(),
+                        is_padding: true,
+                    });
+                    cursor = end;
+                }
+                StructEntry::Upvar(_) | StructEntry::Local(_) => {}
+            }
+        }
+
+        Some(report)
+    }
+}
+
+fn round_up(value: u64, alignment: u64) -> u64 {
+    if alignment == 0 {
+        return value;
+    }
+    value.div_ceil(alignment) * alignment
 }